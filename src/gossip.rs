@@ -0,0 +1,192 @@
+//! UDP gossip-based peer discovery so multiple replicas of this demo (e.g.
+//! several pods on OpenShift) can see each other and aggregate basic metrics.
+//! Each instance broadcasts a small heartbeat to a configured seed list and
+//! keeps a membership table of peers seen within a TTL; see `/cluster`.
+//!
+//! This assumes `GOSSIP_PORT` is only reachable from trusted peers in the
+//! same cluster namespace, same as the rest of this demo's endpoints; it's
+//! not designed to withstand a hostile network.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    env,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::net::UdpSocket;
+
+/// Bump whenever the wire format changes; peers on a different version are
+/// rejected rather than misinterpreted.
+const PROTOCOL_VERSION: u8 = 1;
+const GOSSIP_INTERVAL: Duration = Duration::from_secs(5);
+const MAX_PACKET_SIZE: usize = 1024;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PeerState {
+    hostname: String,
+    uptime_seconds: u64,
+    mem_used_mb: u64,
+    cpu_count: usize,
+    #[serde(skip, default = "Instant::now")]
+    last_seen: Instant,
+}
+
+pub type Membership = Arc<Mutex<HashMap<String, PeerState>>>;
+
+fn encode(msg: &PeerState) -> std::io::Result<Vec<u8>> {
+    let body = serde_json::to_vec(msg)?;
+    let mut frame = Vec::with_capacity(1 + 4 + body.len());
+    frame.push(PROTOCOL_VERSION);
+    frame.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&body);
+    Ok(frame)
+}
+
+fn decode(buf: &[u8]) -> Option<PeerState> {
+    if buf.len() < 5 || buf[0] != PROTOCOL_VERSION {
+        return None;
+    }
+    let len = u32::from_be_bytes(buf[1..5].try_into().ok()?) as usize;
+    let body = buf.get(5..5 + len)?;
+    serde_json::from_slice(body).ok()
+}
+
+fn parse_peers() -> Vec<SocketAddr> {
+    env::var("GOSSIP_PEERS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| s.parse().ok())
+        .collect()
+}
+
+/// Spawns the send/receive loops and returns the shared membership table.
+/// Binds `GOSSIP_PORT` (default 7946); no-op (empty membership) if the port
+/// fails to bind. With no seeds configured nothing is sent, but peers that
+/// announce to this instance are still recorded.
+pub async fn spawn(hostname: String) -> Membership {
+    let membership: Membership = Arc::new(Mutex::new(HashMap::new()));
+
+    let port: u16 = env::var("GOSSIP_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(7946);
+
+    let socket = match UdpSocket::bind(("0.0.0.0", port)).await {
+        Ok(s) => Arc::new(s),
+        Err(e) => {
+            eprintln!("gossip: failed to bind UDP port {port}: {e}");
+            return membership;
+        }
+    };
+
+    let peers = parse_peers();
+
+    // Broadcaster: periodically announce this instance to every seed.
+    {
+        let socket = socket.clone();
+        tokio::spawn(async move {
+            let mut sys = sysinfo::System::new_all();
+            loop {
+                sys.refresh_all();
+                let msg = PeerState {
+                    hostname: hostname.clone(),
+                    uptime_seconds: crate::uptime_secs(),
+                    mem_used_mb: sys.used_memory() / 1024 / 1024,
+                    cpu_count: sys.cpus().len(),
+                    last_seen: Instant::now(),
+                };
+                if let Ok(frame) = encode(&msg) {
+                    for peer in &peers {
+                        let _ = socket.send_to(&frame, peer).await;
+                    }
+                }
+                tokio::time::sleep(GOSSIP_INTERVAL).await;
+            }
+        });
+    }
+
+    // Receiver: record/update membership, dropping malformed or stale-version
+    // frames. Keyed by the observed source address rather than the packet's
+    // self-reported hostname, so a peer can only ever overwrite its own
+    // entry instead of impersonating another member by asserting its name.
+    {
+        let socket = socket.clone();
+        let membership = membership.clone();
+        tokio::spawn(async move {
+            let mut buf = [0u8; MAX_PACKET_SIZE];
+            loop {
+                let (len, from) = match socket.recv_from(&mut buf).await {
+                    Ok(v) => v,
+                    Err(_) => continue,
+                };
+                if let Some(mut peer) = decode(&buf[..len]) {
+                    peer.last_seen = Instant::now();
+                    membership.lock().unwrap().insert(from.to_string(), peer);
+                }
+            }
+        });
+    }
+
+    // Reaper: drop peers not seen within 3x the gossip interval.
+    {
+        let membership = membership.clone();
+        tokio::spawn(async move {
+            let ttl = GOSSIP_INTERVAL * 3;
+            loop {
+                tokio::time::sleep(GOSSIP_INTERVAL).await;
+                membership
+                    .lock()
+                    .unwrap()
+                    .retain(|_, peer| peer.last_seen.elapsed() < ttl);
+            }
+        });
+    }
+
+    membership
+}
+
+#[derive(Serialize)]
+pub struct ClusterView {
+    peers: Vec<PeerSummary>,
+    peer_count: usize,
+    total_mem_used_mb: u64,
+    total_uptime_seconds: u64,
+}
+
+#[derive(Serialize)]
+struct PeerSummary {
+    hostname: String,
+    uptime_seconds: u64,
+    mem_used_mb: u64,
+    cpu_count: usize,
+    last_seen_secs_ago: f64,
+}
+
+pub fn view(membership: &Membership) -> ClusterView {
+    let peers: Vec<PeerSummary> = membership
+        .lock()
+        .unwrap()
+        .values()
+        .map(|p| PeerSummary {
+            hostname: p.hostname.clone(),
+            uptime_seconds: p.uptime_seconds,
+            mem_used_mb: p.mem_used_mb,
+            cpu_count: p.cpu_count,
+            last_seen_secs_ago: p.last_seen.elapsed().as_secs_f64(),
+        })
+        .collect();
+
+    let total_mem_used_mb = peers.iter().map(|p| p.mem_used_mb).sum();
+    let total_uptime_seconds = peers.iter().map(|p| p.uptime_seconds).sum();
+
+    ClusterView {
+        peer_count: peers.len(),
+        peers,
+        total_mem_used_mb,
+        total_uptime_seconds,
+    }
+}
@@ -1,17 +1,56 @@
+mod gossip;
+mod monitor;
+mod timings;
+mod tls;
+
 use axum::{
-    extract::Query,
+    extract::{FromRef, Query, State},
     http::StatusCode,
+    middleware,
     response::{Html, IntoResponse, Json},
     routing::get,
     Router,
 };
 use chrono::Utc;
+use gossip::Membership;
+use monitor::Monitor;
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, env, net::SocketAddr, time::Instant};
 use sysinfo::System;
+use timings::Timings;
 
 static mut START_TIME: Option<Instant> = None;
 
+/// Combined Axum router state. Individual handlers extract just the piece
+/// they need (`State<Timings>`, `State<Membership>`, `State<Monitor>`) via
+/// `FromRef` below.
+#[derive(Clone)]
+struct AppState {
+    timings: Timings,
+    membership: Membership,
+    monitor: Monitor,
+}
+
+impl FromRef<AppState> for Timings {
+    fn from_ref(state: &AppState) -> Self {
+        state.timings.clone()
+    }
+}
+
+impl FromRef<AppState> for Membership {
+    fn from_ref(state: &AppState) -> Self {
+        state.membership.clone()
+    }
+}
+
+impl FromRef<AppState> for Monitor {
+    fn from_ref(state: &AppState) -> Self {
+        state.monitor.clone()
+    }
+}
+
 #[derive(Serialize)]
 struct HealthResponse {
     status: String,
@@ -41,13 +80,15 @@ struct SystemInfo {
 #[derive(Serialize)]
 struct FibResult {
     n: u64,
-    result: u64,
+    result: String,
+    mode: &'static str,
     computation_ms: f64,
 }
 
 #[derive(Deserialize)]
 struct FibQuery {
     n: Option<u64>,
+    mode: Option<String>,
 }
 
 fn uptime_secs() -> u64 {
@@ -119,8 +160,8 @@ async fn landing_page() -> Html<String> {
     </div>
     <div class="card">
       <h3>🧮 Fibonacci</h3>
-      <a href="/fib?n=40">/fib?n=40</a>
-      <p>CPU stress test via naive recursion</p>
+      <a href="/fib?n=100000">/fib?n=100000</a>
+      <p>Fast-doubling bignum; add &amp;mode=naive for a CPU stress test</p>
     </div>
     <div class="card">
       <h3>💥 Crash Test</h3>
@@ -132,6 +173,16 @@ async fn landing_page() -> Html<String> {
       <a href="/metrics">/metrics</a>
       <p>Prometheus-style metrics</p>
     </div>
+    <div class="card">
+      <h3>🔗 Cluster</h3>
+      <a href="/cluster">/cluster</a>
+      <p>Gossip membership across pods</p>
+    </div>
+    <div class="card">
+      <h3>🌐 Upstream Status</h3>
+      <a href="/status">/status</a>
+      <p>Health of configured upstream dependencies</p>
+    </div>
   </div>
 
   <p class="footer">Built with Axum &bull; Compiled with musl &bull; Running from scratch</p>
@@ -153,12 +204,30 @@ async fn healthz() -> Json<HealthResponse> {
     })
 }
 
-async fn readyz() -> Json<HealthResponse> {
-    Json(HealthResponse {
-        status: "ready".into(),
-        uptime_seconds: uptime_secs(),
-        timestamp: Utc::now().to_rfc3339(),
-    })
+async fn readyz(State(monitor): State<Monitor>) -> impl IntoResponse {
+    if monitor.any_down() {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(HealthResponse {
+                status: "not ready".into(),
+                uptime_seconds: uptime_secs(),
+                timestamp: Utc::now().to_rfc3339(),
+            }),
+        )
+    } else {
+        (
+            StatusCode::OK,
+            Json(HealthResponse {
+                status: "ready".into(),
+                uptime_seconds: uptime_secs(),
+                timestamp: Utc::now().to_rfc3339(),
+            }),
+        )
+    }
+}
+
+async fn status(State(monitor): State<Monitor>) -> Html<String> {
+    Html(monitor.render_html())
 }
 
 async fn info() -> Json<ContainerInfo> {
@@ -197,22 +266,76 @@ async fn info() -> Json<ContainerInfo> {
     })
 }
 
-fn fib(n: u64) -> u64 {
-    if n <= 1 { return n; }
-    fib(n - 1) + fib(n - 2)
+/// Naive O(2^n) recursion, kept behind `?mode=naive` for the restart/load
+/// demos that want to actually burn CPU. Capped at 45 to avoid heat death.
+fn fib_naive(n: u64) -> u64 {
+    if n <= 1 {
+        return n;
+    }
+    fib_naive(n - 1) + fib_naive(n - 2)
+}
+
+/// Fast-doubling: walks the bits of `n` from most- to least-significant,
+/// maintaining `(a, b) = (F(k), F(k+1))`. Each step computes `F(2k)` and
+/// `F(2k+1)` from `(a, b)`, then folds in the next bit, giving O(log n)
+/// bignum multiplications instead of O(2^n) additions.
+fn fib_fast_doubling(n: u64) -> BigUint {
+    let mut a = BigUint::zero();
+    let mut b = BigUint::one();
+
+    for i in (0..u64::BITS - n.leading_zeros()).rev() {
+        let c = &a * (&b * 2u32 - &a);
+        let d = &a * &a + &b * &b;
+        if (n >> i) & 1 == 0 {
+            a = c;
+            b = d;
+        } else {
+            a = d.clone();
+            b = c + d;
+        }
+    }
+
+    a
 }
 
+/// F(n) has roughly 0.69*n bits, so even fast-doubling's O(log n) bignum
+/// multiplications get expensive for huge n. Cap it well above anything a
+/// demo needs (the result is already a roughly 209,000-digit number at this
+/// size) so a single unauthenticated request can't force a multi-gigabyte
+/// allocation or pin the CPU.
+const MAX_FAST_DOUBLING_N: u64 = 1_000_000;
+
 async fn fibonacci(Query(params): Query<FibQuery>) -> impl IntoResponse {
-    let n = params.n.unwrap_or(10).min(45); // Cap at 45 to avoid heat death
+    let n = params.n.unwrap_or(10);
+    let naive = params.mode.as_deref() == Some("naive");
+
+    if !naive && n > MAX_FAST_DOUBLING_N {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("n must be <= {MAX_FAST_DOUBLING_N}"),
+        )
+            .into_response();
+    }
+
+    let n = if naive { n.min(45) } else { n };
+
     let start = Instant::now();
-    let result = fib(n);
+
+    let (result, mode) = if naive {
+        (fib_naive(n).to_string(), "naive")
+    } else {
+        (fib_fast_doubling(n).to_string(), "fast-doubling")
+    };
+
     let elapsed = start.elapsed().as_secs_f64() * 1000.0;
 
     Json(FibResult {
         n,
         result,
+        mode,
         computation_ms: elapsed,
     })
+    .into_response()
 }
 
 async fn crash() -> impl IntoResponse {
@@ -265,6 +388,10 @@ app_cpu_count {}
     )
 }
 
+async fn cluster(State(membership): State<Membership>) -> Json<gossip::ClusterView> {
+    Json(gossip::view(&membership))
+}
+
 // ─── Main ───────────────────────────────────────────────────
 
 #[tokio::main]
@@ -276,18 +403,69 @@ async fn main() {
         .parse()
         .unwrap_or(8080);
 
-    let app = Router::new()
+    // Request timing is opt-in: the middleware layer and background sampler
+    // add a mutex lock per request, so keep them out of the hot path unless
+    // TIMINGS=1 is explicitly set. The `Timings` handle itself is cheap to
+    // construct either way, which keeps the router's state type uniform.
+    let timings_enabled = env::var("TIMINGS").as_deref() == Ok("1");
+    let timings = Timings::new();
+
+    let hostname = hostname::get()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "unknown".into());
+    let membership = gossip::spawn(hostname).await;
+    let monitor = monitor::spawn();
+
+    let mut app = Router::new()
         .route("/", get(landing_page))
         .route("/healthz", get(healthz))
         .route("/readyz", get(readyz))
         .route("/info", get(info))
         .route("/fib", get(fibonacci))
         .route("/crash", get(crash))
-        .route("/metrics", get(metrics));
+        .route("/metrics", get(metrics))
+        .route("/cluster", get(cluster))
+        .route("/status", get(status));
+
+    if timings_enabled {
+        timings::spawn_sampler(timings.clone());
+        app = app
+            .route("/timings", get(timings::timings_html))
+            .route("/timings.json", get(timings::timings_json))
+            .layer(middleware::from_fn_with_state(
+                timings.clone(),
+                timings::record_request,
+            ));
+    }
+
+    let app = app.with_state(AppState {
+        timings,
+        membership,
+        monitor,
+    });
 
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
     println!("🦀 Rust demo listening on http://{}", addr);
 
+    let tls = match tls::load() {
+        Ok(tls) => tls,
+        Err(e) => {
+            eprintln!("tls: {e}");
+            std::process::exit(1);
+        }
+    };
+
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    let http = axum::serve(listener, app.clone());
+
+    match tls {
+        Some(tls) => {
+            println!("🔒 TLS listening on https://{}", tls.addr);
+            let https = axum_server::bind_rustls(tls.addr, tls.config).serve(app.into_make_service());
+            let (http_res, https_res) = tokio::join!(http, https);
+            http_res.unwrap();
+            https_res.unwrap();
+        }
+        None => http.await.unwrap(),
+    }
 }
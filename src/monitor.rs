@@ -0,0 +1,246 @@
+//! Upstream-dependency health checking. Reads a list of URLs from
+//! `CHECK_URLS`, polls each on a fixed interval, classifies it as
+//! Up/Slow/Down based on an RTT threshold, and fires a webhook alert on
+//! state transitions (not on every poll). `/readyz` consults the latest
+//! snapshot so readiness actually reflects upstream health.
+
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    env,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(3);
+const DEFAULT_SLOW_THRESHOLD_MS: f64 = 500.0;
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "state")]
+pub enum EndpointStatus {
+    Up { rtt_ms: f64 },
+    Slow { rtt_ms: f64 },
+    Down { error: String },
+}
+
+impl EndpointStatus {
+    fn is_required(&self) -> bool {
+        !matches!(self, EndpointStatus::Up { .. } | EndpointStatus::Slow { .. })
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct EndpointSnapshot {
+    url: String,
+    status: EndpointStatus,
+    checked_at: String,
+}
+
+struct Inner {
+    snapshot: Mutex<HashMap<String, EndpointSnapshot>>,
+}
+
+#[derive(Clone)]
+pub struct Monitor(Arc<Inner>);
+
+impl Monitor {
+    fn new() -> Self {
+        Monitor(Arc::new(Inner {
+            snapshot: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    pub fn snapshot(&self) -> Vec<EndpointSnapshot> {
+        let mut entries: Vec<_> = self.0.snapshot.lock().unwrap().values().cloned().collect();
+        entries.sort_by(|a, b| a.url.cmp(&b.url));
+        entries
+    }
+
+    /// Renders the latest snapshot as a small HTML page, grouped by state.
+    pub fn render_html(&self) -> String {
+        let entries = self.snapshot();
+
+        let group = |label: &str, color: &str, pred: &dyn Fn(&EndpointStatus) -> bool| -> String {
+            let rows: String = entries
+                .iter()
+                .filter(|e| pred(&e.status))
+                .map(|e| {
+                    let detail = match &e.status {
+                        EndpointStatus::Up { rtt_ms } | EndpointStatus::Slow { rtt_ms } => {
+                            format!("{rtt_ms:.1}ms")
+                        }
+                        EndpointStatus::Down { error } => error.clone(),
+                    };
+                    format!(
+                        "<li><code>{}</code> &mdash; {} <span style=\"color:#8b949e\">({})</span></li>",
+                        e.url, detail, e.checked_at
+                    )
+                })
+                .collect();
+            if rows.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    "<h3 style=\"color:{color}\">{label}</h3><ul>{rows}</ul>",
+                )
+            }
+        };
+
+        format!(
+            r##"<!DOCTYPE html>
+<html lang="en">
+<head><meta charset="utf-8"><title>Upstream status</title>
+<style>
+  body {{ font-family: 'Segoe UI', system-ui, sans-serif; background: #0d1117; color: #c9d1d9; padding: 2rem; }}
+  h1 {{ font-size: 1.4rem; }}
+  ul {{ margin: 0 0 1.5rem 1.25rem; }}
+  code {{ color: #e44d26; }}
+</style>
+</head>
+<body>
+<h1>Upstream dependency status</h1>
+{up}
+{slow}
+{down}
+</body>
+</html>"##,
+            up = group("Up", "#3fb950", &|s| matches!(s, EndpointStatus::Up { .. })),
+            slow = group("Slow", "#d29922", &|s| matches!(s, EndpointStatus::Slow { .. })),
+            down = group("Down", "#f85149", &|s| matches!(s, EndpointStatus::Down { .. })),
+        )
+    }
+
+    /// Any monitored endpoint is Down. Used by `/readyz`; empty checklist
+    /// (no `CHECK_URLS` configured) is always ready.
+    pub fn any_down(&self) -> bool {
+        self.0
+            .snapshot
+            .lock()
+            .unwrap()
+            .values()
+            .any(|e| matches!(e.status, EndpointStatus::Down { .. }))
+    }
+}
+
+fn parse_urls() -> Vec<String> {
+    env::var("CHECK_URLS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn slow_threshold_ms() -> f64 {
+    env::var("CHECK_SLOW_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_SLOW_THRESHOLD_MS)
+}
+
+async fn check_one(client: &reqwest::Client, url: &str, slow_threshold_ms: f64) -> EndpointStatus {
+    let start = Instant::now();
+    match client.get(url).send().await {
+        Ok(_) => {
+            let rtt_ms = start.elapsed().as_secs_f64() * 1000.0;
+            if rtt_ms > slow_threshold_ms {
+                EndpointStatus::Slow { rtt_ms }
+            } else {
+                EndpointStatus::Up { rtt_ms }
+            }
+        }
+        Err(e) => EndpointStatus::Down {
+            error: e.to_string(),
+        },
+    }
+}
+
+/// Fires the alert in the background so a slow/hanging webhook endpoint
+/// can't stall the poller's checks of the other `CHECK_URLS` entries.
+/// Reuses the poller's `client` (timeout + no-redirect already configured)
+/// rather than building a fresh, unbounded one per alert.
+fn fire_webhook(client: reqwest::Client, webhook_url: String, url: String, status: EndpointStatus) {
+    tokio::spawn(async move {
+        let payload = serde_json::json!({
+            "url": url,
+            "status": status,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        });
+        if let Err(e) = client.post(&webhook_url).json(&payload).send().await {
+            eprintln!("monitor: failed to deliver webhook alert for {url}: {e}");
+        }
+    });
+}
+
+fn same_variant(a: &EndpointStatus, b: &EndpointStatus) -> bool {
+    matches!(
+        (a, b),
+        (EndpointStatus::Up { .. }, EndpointStatus::Up { .. })
+            | (EndpointStatus::Slow { .. }, EndpointStatus::Slow { .. })
+            | (EndpointStatus::Down { .. }, EndpointStatus::Down { .. })
+    )
+}
+
+/// Spawns the background poller and returns the shared `Monitor` handle.
+/// If `CHECK_URLS` is unset the poller is a no-op and `/readyz` stays ready.
+pub fn spawn() -> Monitor {
+    let monitor = Monitor::new();
+    let urls = parse_urls();
+    if urls.is_empty() {
+        return monitor;
+    }
+
+    let webhook_url = env::var("CHECK_WEBHOOK_URL").ok();
+    let slow_threshold_ms = slow_threshold_ms();
+    // Uses reqwest's rustls-tls-native-roots backend, so outbound checks
+    // against public upstreams trust the same root store as the host.
+    let client = match reqwest::Client::builder()
+        .timeout(DEFAULT_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("monitor: failed to build HTTP client, upstream checks disabled: {e}");
+            return monitor;
+        }
+    };
+
+    {
+        let monitor = monitor.clone();
+        tokio::spawn(async move {
+            loop {
+                for url in &urls {
+                    let status = check_one(&client, url, slow_threshold_ms).await;
+                    let transitioned = {
+                        let mut snapshot = monitor.0.snapshot.lock().unwrap();
+                        let changed = match snapshot.get(url) {
+                            Some(prev) => !same_variant(&prev.status, &status),
+                            None => status.is_required() || matches!(status, EndpointStatus::Slow { .. }),
+                        };
+                        snapshot.insert(
+                            url.clone(),
+                            EndpointSnapshot {
+                                url: url.clone(),
+                                status: status.clone(),
+                                checked_at: chrono::Utc::now().to_rfc3339(),
+                            },
+                        );
+                        changed
+                    };
+
+                    if transitioned && !matches!(status, EndpointStatus::Up { .. }) {
+                        if let Some(webhook_url) = &webhook_url {
+                            fire_webhook(client.clone(), webhook_url.clone(), url.clone(), status.clone());
+                        }
+                    }
+                }
+                tokio::time::sleep(CHECK_INTERVAL).await;
+            }
+        });
+    }
+
+    monitor
+}
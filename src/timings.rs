@@ -0,0 +1,231 @@
+//! Crate-wide request timing, mirroring cargo's `--timings` compile reporter:
+//! every request is recorded into a bounded ring buffer and can be viewed as
+//! either a self-contained HTML timeline or raw JSON. Capture is gated behind
+//! the `TIMINGS` env var (see `main`) so the middleware and sampler are never
+//! installed, and therefore cost nothing, unless explicitly enabled.
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    middleware::Next,
+    response::{Html, IntoResponse, Json, Response},
+};
+use serde::Serialize;
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+use sysinfo::System;
+
+const MAX_ENTRIES: usize = 500;
+const MAX_SAMPLES: usize = 900; // ~15 minutes at one sample/sec
+
+#[derive(Clone, Serialize)]
+pub struct UnitTime {
+    path: String,
+    status: u16,
+    start_offset_ms: f64,
+    duration_ms: f64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ResourceSample {
+    timestamp_ms: f64,
+    mem_used_mb: u64,
+    cpu_pct: f32,
+}
+
+#[derive(Serialize)]
+pub struct TimingsSnapshot {
+    entries: Vec<UnitTime>,
+    samples: Vec<ResourceSample>,
+}
+
+struct Inner {
+    started: Instant,
+    entries: Mutex<Vec<UnitTime>>,
+    samples: Mutex<Vec<ResourceSample>>,
+}
+
+/// Shared handle plugged into the `Router` as Axum state; cheap to clone.
+#[derive(Clone)]
+pub struct Timings(Arc<Inner>);
+
+impl Timings {
+    pub fn new() -> Self {
+        Timings(Arc::new(Inner {
+            started: Instant::now(),
+            entries: Mutex::new(Vec::new()),
+            samples: Mutex::new(Vec::new()),
+        }))
+    }
+
+    fn record(&self, path: String, status: u16, start_offset_ms: f64, duration_ms: f64) {
+        let mut entries = self.0.entries.lock().unwrap();
+        entries.push(UnitTime {
+            path,
+            status,
+            start_offset_ms,
+            duration_ms,
+        });
+        let overflow = entries.len().saturating_sub(MAX_ENTRIES);
+        if overflow > 0 {
+            entries.drain(0..overflow);
+        }
+    }
+
+    fn sample(&self, mem_used_mb: u64, cpu_pct: f32) {
+        let timestamp_ms = self.0.started.elapsed().as_secs_f64() * 1000.0;
+        let mut samples = self.0.samples.lock().unwrap();
+        samples.push(ResourceSample {
+            timestamp_ms,
+            mem_used_mb,
+            cpu_pct,
+        });
+        let overflow = samples.len().saturating_sub(MAX_SAMPLES);
+        if overflow > 0 {
+            samples.drain(0..overflow);
+        }
+    }
+
+    fn snapshot(&self) -> (Vec<UnitTime>, Vec<ResourceSample>) {
+        (
+            self.0.entries.lock().unwrap().clone(),
+            self.0.samples.lock().unwrap().clone(),
+        )
+    }
+}
+
+/// Axum middleware layer: wraps every request with a start/duration capture.
+pub async fn record_request(State(timings): State<Timings>, req: Request<Body>, next: Next) -> Response {
+    let path = req.uri().path().to_string();
+    let start_offset_ms = timings.0.started.elapsed().as_secs_f64() * 1000.0;
+    let start = Instant::now();
+
+    let response = next.run(req).await;
+
+    let duration_ms = start.elapsed().as_secs_f64() * 1000.0;
+    timings.record(path, response.status().as_u16(), start_offset_ms, duration_ms);
+    response
+}
+
+/// Background task sampling CPU/memory once a second for the timeline chart.
+pub fn spawn_sampler(timings: Timings) {
+    tokio::spawn(async move {
+        let mut sys = System::new_all();
+        loop {
+            sys.refresh_memory();
+            sys.refresh_cpu_usage();
+            timings.sample(sys.used_memory() / 1024 / 1024, sys.global_cpu_usage());
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+    });
+}
+
+pub async fn timings_json(State(timings): State<Timings>) -> impl IntoResponse {
+    let (entries, samples) = timings.snapshot();
+    Json(TimingsSnapshot { entries, samples })
+}
+
+/// Escapes the handful of characters that matter inside an HTML attribute
+/// value. `path` comes straight from the request URI, so it must be escaped
+/// before landing in the `/timings` page template.
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub async fn timings_html(State(timings): State<Timings>) -> Html<String> {
+    let (entries, samples) = timings.snapshot();
+
+    let max_end_ms = entries
+        .iter()
+        .map(|e| e.start_offset_ms + e.duration_ms)
+        .chain(samples.iter().map(|s| s.timestamp_ms))
+        .fold(1.0_f64, f64::max);
+
+    let bars: String = entries
+        .iter()
+        .enumerate()
+        .map(|(i, e)| {
+            let left = e.start_offset_ms / max_end_ms * 100.0;
+            let width = (e.duration_ms / max_end_ms * 100.0).max(0.15);
+            let top = 4 + (i % 60) * 5;
+            let color = if e.status >= 500 {
+                "#f85149"
+            } else if e.status >= 400 {
+                "#d29922"
+            } else {
+                "#3fb950"
+            };
+            format!(
+                r#"<div class="bar" style="left:{left:.3}%;width:{width:.3}%;top:{top}px;background:{color}" title="{path} [{status}] {duration:.2}ms"></div>"#,
+                path = escape_html(&e.path),
+                status = e.status,
+                duration = e.duration_ms,
+            )
+        })
+        .collect();
+
+    let mem_points: String = samples
+        .iter()
+        .map(|s| {
+            format!(
+                "{:.3},{:.3}",
+                s.timestamp_ms / max_end_ms * 100.0,
+                100.0 - (s.mem_used_mb as f64).min(4096.0) / 4096.0 * 100.0
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let cpu_points: String = samples
+        .iter()
+        .map(|s| {
+            format!(
+                "{:.3},{:.3}",
+                s.timestamp_ms / max_end_ms * 100.0,
+                100.0 - (s.cpu_pct as f64).clamp(0.0, 100.0)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let html = format!(
+        r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Request timings</title>
+<style>
+  body {{ font-family: 'Segoe UI', system-ui, sans-serif; background: #0d1117; color: #c9d1d9; padding: 2rem; }}
+  h1 {{ font-size: 1.4rem; margin-bottom: 1rem; }}
+  .chart {{ position: relative; height: 320px; background: #161b22; border: 1px solid #30363d; border-radius: 8px; overflow: hidden; }}
+  .bar {{ position: absolute; height: 4px; border-radius: 2px; }}
+  .bg-line {{ position: absolute; left: 0; top: 0; width: 100%; height: 100%; }}
+  .count {{ color: #8b949e; font-size: 0.85rem; margin-top: 0.5rem; }}
+</style>
+</head>
+<body>
+<h1>Request timeline &mdash; {count} requests</h1>
+<div class="chart">
+  <svg class="bg-line" viewBox="0 0 100 100" preserveAspectRatio="none">
+    <polyline fill="none" stroke="#58a6ff" stroke-width="0.5" points="{mem_points}" />
+    <polyline fill="none" stroke="#d29922" stroke-width="0.5" points="{cpu_points}" />
+  </svg>
+  {bars}
+</div>
+<p class="count">
+  <span style="color:#58a6ff">&mdash;</span> memory &nbsp;
+  <span style="color:#d29922">&mdash;</span> CPU &nbsp;
+  See <a href="/timings.json" style="color:#e44d26">/timings.json</a> for the raw data.
+</p>
+</body>
+</html>"##,
+        count = entries.len(),
+    );
+
+    Html(html)
+}
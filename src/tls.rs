@@ -0,0 +1,65 @@
+//! Optional TLS termination. When `TLS_CERT`/`TLS_KEY` point at PEM files
+//! (the usual OpenShift mounted-secret layout) the server loads them with
+//! `rustls` and serves HTTPS on `TLS_PORT` alongside the existing plain-HTTP
+//! listener on `PORT`; otherwise TLS is simply not started.
+
+use axum_server::tls_rustls::RustlsConfig;
+use rustls::ServerConfig;
+use std::{env, fs::File, io::BufReader, net::SocketAddr, sync::Arc};
+
+/// Where to terminate TLS, if configured at all via `TLS_CERT`/`TLS_KEY`.
+pub struct TlsSettings {
+    pub addr: SocketAddr,
+    pub config: RustlsConfig,
+}
+
+fn load_cert_chain(path: &str) -> std::io::Result<Vec<rustls_pki_types::CertificateDer<'static>>> {
+    let file = File::open(path)?;
+    rustls_pemfile::certs(&mut BufReader::new(file)).collect()
+}
+
+fn load_private_key(path: &str) -> std::io::Result<rustls_pki_types::PrivateKeyDer<'static>> {
+    let file = File::open(path)?;
+    rustls_pemfile::private_key(&mut BufReader::new(file))?
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "no private key found in TLS_KEY"))
+}
+
+/// Returns `Ok(None)` when TLS isn't configured (`TLS_CERT`/`TLS_KEY` unset),
+/// `Ok(Some(..))` when it loaded successfully, and `Err` with a readable
+/// message when the files are present but unparseable.
+pub fn load() -> Result<Option<TlsSettings>, String> {
+    let cert_var = env::var("TLS_CERT");
+    let key_var = env::var("TLS_KEY");
+    let (cert_path, key_path) = match (cert_var, key_var) {
+        (Ok(cert), Ok(key)) => (cert, key),
+        (Err(_), Err(_)) => return Ok(None),
+        (cert, key) => {
+            eprintln!(
+                "tls: TLS_CERT ({}) and TLS_KEY ({}) must both be set to enable TLS; falling back to plain HTTP",
+                if cert.is_ok() { "set" } else { "unset" },
+                if key.is_ok() { "set" } else { "unset" },
+            );
+            return Ok(None);
+        }
+    };
+
+    let certs = load_cert_chain(&cert_path)
+        .map_err(|e| format!("failed to read/parse TLS_CERT ({cert_path}): {e}"))?;
+    let key = load_private_key(&key_path)
+        .map_err(|e| format!("failed to read/parse TLS_KEY ({key_path}): {e}"))?;
+
+    let server_config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("invalid TLS certificate/key pair: {e}"))?;
+
+    let port: u16 = env::var("TLS_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(8443);
+
+    Ok(Some(TlsSettings {
+        addr: SocketAddr::from(([0, 0, 0, 0], port)),
+        config: RustlsConfig::from_config(Arc::new(server_config)),
+    }))
+}